@@ -5,28 +5,47 @@
 
 use std::collections::HashSet;
 use std::str::Utf8Error;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 
 use crate::resource_storage::BraveCoreResourceStorage;
 use adblock::lists::FilterSet as InnerFilterSet;
-use adblock::resources::{InMemoryResourceStorage, Resource};
+use adblock::resources::{InMemoryResourceStorage, MimeType, Resource, ResourceType};
 use adblock::url_parser::ResolvesDomain;
 use adblock::Engine as InnerEngine;
 use cxx::{let_cxx_string, CxxString, CxxVector};
 
 use crate::ffi::{
-    resolve_domain_position, BlockerResult, BoxEngineResult, ContentBlockingRulesResult, DebugInfo,
-    FilterListMetadata, RegexManagerDiscardPolicy, VecStringResult,
+    resolve_domain_position, BlockerResult, BoxEngineResult, ContentBlockingRulesResult, CspResult,
+    DebugInfo, FilterListMetadata, MatchStats, OptionalResource, OptionalString, RegexDebugEntry,
+    RegexManagerDiscardPolicy, ResourceInfo, VecStringResult,
 };
 use crate::filter_set::FilterSet;
+use crate::request::Request;
 use crate::result::InternalError;
 
 #[cfg(feature = "ios")]
-use crate::ffi::ContentBlockingRules;
+use crate::ffi::{
+    ContentBlockingRules, ContentBlockingRulesChunkedResult, ContentBlockingRulesSplitResult,
+    ResultKind,
+};
+
+/// Cumulative, engine-wide match counters maintained across the lifetime of
+/// an `Engine`, for `brave://adblock`-style debugging. The counters are not
+/// broken down per filter or per list; the matching filter of each check is
+/// reported in `BlockerResult::filter` instead.
+#[derive(Default)]
+struct MatchCounters {
+    total_checks: AtomicU64,
+    total_matches: AtomicU64,
+    exception_hits: AtomicU64,
+}
 
 /// Wrapper around the adblock engine.
 pub struct Engine {
     engine: InnerEngine,
+    stats: MatchCounters,
+    scriptlet_debug: AtomicBool,
 }
 
 impl Default for Box<Engine> {
@@ -41,7 +60,11 @@ impl Default for Box<Engine> {
 ///
 /// A `Box<Engine>` containing the new, empty engine.
 pub fn new_engine() -> Box<Engine> {
-    Box::new(Engine { engine: InnerEngine::default() })
+    Box::new(Engine {
+        engine: InnerEngine::default(),
+        stats: MatchCounters::default(),
+        scriptlet_debug: AtomicBool::new(false),
+    })
 }
 
 /// Creates a new engine with rules from a given filter list.
@@ -58,7 +81,11 @@ pub fn engine_with_rules(rules: &CxxVector<u8>) -> BoxEngineResult {
         let mut filter_set = InnerFilterSet::new(false);
         filter_set.add_filter_list(std::str::from_utf8(rules.as_slice())?, Default::default());
         let engine = InnerEngine::from_filter_set(filter_set, true);
-        Ok(Box::new(Engine { engine }))
+        Ok(Box::new(Engine {
+            engine,
+            stats: MatchCounters::default(),
+            scriptlet_debug: AtomicBool::new(false),
+        }))
     }()
     .into()
 }
@@ -75,7 +102,11 @@ pub fn engine_with_rules(rules: &CxxVector<u8>) -> BoxEngineResult {
 pub fn engine_from_filter_set(filter_set: Box<FilterSet>) -> BoxEngineResult {
     || -> Result<Box<Engine>, InternalError> {
         let engine = InnerEngine::from_filter_set(filter_set.0, true);
-        Ok(Box::new(Engine { engine }))
+        Ok(Box::new(Engine {
+            engine,
+            stats: MatchCounters::default(),
+            scriptlet_debug: AtomicBool::new(false),
+        }))
     }()
     .into()
 }
@@ -114,26 +145,36 @@ pub fn read_list_metadata(list: &CxxVector<u8>) -> FilterListMetadata {
         .unwrap_or_default()
 }
 
-/// Converts a list in adblock syntax to its corresponding iOS content-blocking syntax.
-///
-/// `truncated` will be set to indicate whether or not some rules had to be removed
-/// to avoid iOS's maximum rule count limit.
+/// This value corresponds to `maxRuleCount` here:
+/// https://github.com/WebKit/WebKit/blob/4a2df13be2253f64d8da58b794d74347a3742652/Source/WebCore/contentextensions/ContentExtensionParser.cpp#L299
+#[cfg(feature = "ios")]
+const MAX_CB_LIST_SIZE: usize = 150000;
+
+/// Converts a list in adblock syntax to its corresponding iOS content-blocking
+/// syntax, splitting the output into as many chunks as needed to keep each
+/// one at or below `max_rules_per_chunk` rules. Every chunk also carries the
+/// first-party document exception rule, which consumes one of that budget's
+/// slots, so `max_rules_per_chunk` is clamped to a minimum of 2 (one network
+/// rule plus the exception rule).
 ///
 /// # Arguments
 ///
 /// * `rules` - The adblock rules to convert.
+/// * `max_rules_per_chunk` - The maximum number of rules allowed per chunk,
+///   clamped to a minimum of 2 to leave room for the exception rule.
 ///
 /// # Returns
 ///
-/// A `ContentBlockingRulesResult` containing the converted rules.
+/// A `ContentBlockingRulesChunkedResult` containing one JSON rule list per chunk.
 #[cfg(feature = "ios")]
-pub fn convert_rules_to_content_blocking(rules: &CxxString) -> ContentBlockingRulesResult {
-    || -> Result<ContentBlockingRules, InternalError> {
+pub fn convert_rules_to_content_blocking_chunked(
+    rules: &CxxString,
+    max_rules_per_chunk: u32,
+) -> ContentBlockingRulesChunkedResult {
+    || -> Result<Vec<String>, InternalError> {
         use adblock::lists::{ParseOptions, RuleTypes};
 
-        /// This value corresponds to `maxRuleCount` here:
-        /// https://github.com/WebKit/WebKit/blob/4a2df13be2253f64d8da58b794d74347a3742652/Source/WebCore/contentextensions/ContentExtensionParser.cpp#L299
-        const MAX_CB_LIST_SIZE: usize = 150000;
+        let max_rules_per_chunk = clamp_max_rules_per_chunk(max_rules_per_chunk);
 
         let mut filter_set = InnerFilterSet::new(true);
         filter_set.add_filter_list(
@@ -143,23 +184,113 @@ pub fn convert_rules_to_content_blocking(rules: &CxxString) -> ContentBlockingRu
 
         // `unwrap` is safe here because `into_content_blocking` only panics if the
         // `FilterSet` was not created in debug mode
-        let (mut cb_rules, _) = filter_set.into_content_blocking().unwrap();
-        let rules_len = cb_rules.len();
-        let truncated = if rules_len > MAX_CB_LIST_SIZE {
-            // Note that the last rule is always the first-party document exception rule,
-            // which we want to keep. Otherwise, we can arbitrarily truncate rules
-            // before that to ensure that the list can actually compile.
-            cb_rules.swap(rules_len - 1, MAX_CB_LIST_SIZE - 1);
-            cb_rules.truncate(MAX_CB_LIST_SIZE);
-            true
-        } else {
-            false
+        let (cb_rules, _) = filter_set.into_content_blocking().unwrap();
+        if cb_rules.is_empty() {
+            return Ok(vec![serde_json::to_string(&cb_rules)?]);
+        }
+
+        // Note that the last rule is always the first-party document exception
+        // rule, which every chunk needs in order to compile with correct
+        // exception semantics.
+        let (cb_rules, exception_rule) = {
+            let mut cb_rules = cb_rules;
+            let exception_rule = cb_rules.pop().expect("cb_rules is non-empty");
+            (cb_rules, exception_rule)
         };
-        Ok(ContentBlockingRules { rules_json: serde_json::to_string(&cb_rules)?, truncated })
+
+        Ok(chunk_content_blocking_rules(cb_rules, exception_rule, max_rules_per_chunk)?)
     }()
     .into()
 }
 
+/// Clamps a caller-supplied `max_rules_per_chunk` to a minimum of 2, since
+/// every chunk must also carry the first-party document exception rule,
+/// which consumes one of that budget's slots.
+#[cfg(feature = "ios")]
+fn clamp_max_rules_per_chunk(max_rules_per_chunk: u32) -> usize {
+    (max_rules_per_chunk as usize).max(2)
+}
+
+/// Splits `cb_rules` into JSON-serialized chunks of at most
+/// `max_rules_per_chunk` rules each, appending `exception_rule` (the
+/// first-party document exception every chunk needs for correct exception
+/// semantics) to every chunk. `max_rules_per_chunk` must be at least 2 (one
+/// network rule plus the exception rule); callers are expected to clamp it
+/// before calling in, so it's asserted here rather than re-clamped.
+///
+/// If the only compiled rule was the exception rule itself, `cb_rules` is
+/// empty; a single chunk containing just the exception rule is still
+/// returned, so the result is always a valid, non-empty rule list.
+#[cfg(feature = "ios")]
+fn chunk_content_blocking_rules<T: serde::Serialize + Clone>(
+    cb_rules: Vec<T>,
+    exception_rule: T,
+    max_rules_per_chunk: usize,
+) -> Result<Vec<String>, serde_json::Error> {
+    debug_assert!(max_rules_per_chunk >= 2, "max_rules_per_chunk must leave room for the exception rule");
+
+    if cb_rules.is_empty() {
+        return Ok(vec![serde_json::to_string(&vec![exception_rule])?]);
+    }
+
+    cb_rules
+        .chunks(max_rules_per_chunk.saturating_sub(1).max(1))
+        .map(|chunk| {
+            let mut chunk = chunk.to_vec();
+            chunk.push(exception_rule.clone());
+            serde_json::to_string(&chunk)
+        })
+        .collect()
+}
+
+/// Converts a list in adblock syntax to its corresponding iOS content-blocking
+/// syntax. This function panics if called on non-iOS platforms.
+#[cfg(not(feature = "ios"))]
+pub fn convert_rules_to_content_blocking_chunked(
+    _rules: &CxxString,
+    _max_rules_per_chunk: u32,
+) -> ContentBlockingRulesChunkedResult {
+    panic!("convert_rules_to_content_blocking_chunked can only be called on iOS");
+}
+
+/// Converts a list in adblock syntax to its corresponding iOS content-blocking syntax.
+///
+/// `truncated` will be set to indicate whether or not some rules had to be removed
+/// to avoid iOS's maximum rule count limit. Implemented for backwards
+/// compatibility on top of `convert_rules_to_content_blocking_chunked` with
+/// `MAX_CB_LIST_SIZE`: only the first chunk is returned, and `truncated` is
+/// set when more than one chunk was produced.
+///
+/// # Arguments
+///
+/// * `rules` - The adblock rules to convert.
+///
+/// # Returns
+///
+/// A `ContentBlockingRulesResult` containing the converted rules.
+#[cfg(feature = "ios")]
+pub fn convert_rules_to_content_blocking(rules: &CxxString) -> ContentBlockingRulesResult {
+    let chunked = convert_rules_to_content_blocking_chunked(rules, MAX_CB_LIST_SIZE as u32);
+    match chunked.result_kind {
+        ResultKind::Success => {
+            let truncated = chunked.value.len() > 1;
+            ContentBlockingRulesResult {
+                value: ContentBlockingRules {
+                    rules_json: chunked.value.into_iter().next().unwrap_or_default(),
+                    truncated,
+                },
+                result_kind: ResultKind::Success,
+                error_message: String::new(),
+            }
+        }
+        _ => ContentBlockingRulesResult {
+            value: ContentBlockingRules::default(),
+            result_kind: chunked.result_kind,
+            error_message: chunked.error_message,
+        },
+    }
+}
+
 /// Converts a list in adblock syntax to its corresponding iOS content-blocking syntax.
 /// This function panics if called on non-iOS platforms.
 #[cfg(not(feature = "ios"))]
@@ -167,6 +298,50 @@ pub fn convert_rules_to_content_blocking(_rules: &CxxString) -> ContentBlockingR
     panic!("convert_rules_to_content_blocking can only be called on iOS");
 }
 
+/// Converts a list in adblock syntax into as many WebKit-maximum-sized
+/// content-blocking rule lists as needed so that registering several
+/// content-blocker extensions loses no rules, instead of truncating at
+/// `MAX_CB_LIST_SIZE`.
+///
+/// # Arguments
+///
+/// * `rules` - The adblock rules to convert.
+///
+/// # Returns
+///
+/// A `ContentBlockingRulesSplitResult` containing one rule list per chunk.
+#[cfg(feature = "ios")]
+pub fn convert_rules_to_content_blocking_split(
+    rules: &CxxString,
+) -> ContentBlockingRulesSplitResult {
+    let chunked = convert_rules_to_content_blocking_chunked(rules, MAX_CB_LIST_SIZE as u32);
+    match chunked.result_kind {
+        ResultKind::Success => ContentBlockingRulesSplitResult {
+            value: chunked
+                .value
+                .into_iter()
+                .map(|rules_json| ContentBlockingRules { rules_json, truncated: false })
+                .collect(),
+            result_kind: ResultKind::Success,
+            error_message: String::new(),
+        },
+        _ => ContentBlockingRulesSplitResult {
+            value: Vec::new(),
+            result_kind: chunked.result_kind,
+            error_message: chunked.error_message,
+        },
+    }
+}
+
+/// Converts a list in adblock syntax into as many content-blocking rule
+/// lists as needed. This function panics if called on non-iOS platforms.
+#[cfg(not(feature = "ios"))]
+pub fn convert_rules_to_content_blocking_split(
+    _rules: &CxxString,
+) -> ContentBlockingRulesSplitResult {
+    panic!("convert_rules_to_content_blocking_split can only be called on iOS");
+}
+
 fn convert_cxx_string_vector_to_string_collection<C>(
     value: &CxxVector<CxxString>,
 ) -> Result<C, Utf8Error>
@@ -176,6 +351,111 @@ where
     value.iter().map(|s| s.to_str().map(|t| t.to_string())).collect()
 }
 
+/// Returns a sensible default mime type for a redirect resource whose own
+/// kind doesn't specify one, based on the type of request being redirected.
+fn default_mime_for_request_type(request_type: &str) -> &'static str {
+    match request_type {
+        "script" => "application/javascript",
+        "stylesheet" => "text/css",
+        "image" | "imageset" => "image/gif",
+        "document" | "subdocument" => "text/html",
+        _ => "text/plain",
+    }
+}
+
+/// Rough number of bytes a compiled regex occupies in memory per byte of its
+/// source pattern, used as a cheap proxy for the regex manager's actual
+/// memory footprint (which isn't exposed by adblock-rust).
+const ESTIMATED_BYTES_PER_PATTERN_BYTE: usize = 16;
+
+/// Estimates the memory footprint of a compiled regex from its source
+/// pattern length. This is a heuristic, not a measurement: adblock-rust
+/// doesn't expose the compiled regex's actual size, and the regex crate's
+/// internal representation can grow non-linearly with pattern complexity.
+fn estimate_regex_bytes(regex: &OptionalString) -> usize {
+    if regex.has_value {
+        regex.value.len() * ESTIMATED_BYTES_PER_PATTERN_BYTE
+    } else {
+        0
+    }
+}
+
+/// Returns whether every length in `others` equals `expected`, for
+/// validating that a batch of parallel arrays describes the same number of
+/// requests before indexing into them.
+fn parallel_batch_lengths_match(expected: usize, others: &[usize]) -> bool {
+    others.iter().all(|&len| len == expected)
+}
+
+/// Picks the least-recently-used regexes to evict (by descending
+/// `unused_secs`) until `total_bytes` would fit within `max_bytes`, returning
+/// their ids in eviction order. Returns no ids if `total_bytes` already fits.
+fn select_regexes_to_evict(
+    mut entries: Vec<RegexDebugEntry>,
+    mut total_bytes: usize,
+    max_bytes: usize,
+) -> Vec<u64> {
+    if total_bytes <= max_bytes {
+        return Vec::new();
+    }
+
+    entries.sort_by(|a, b| b.unused_secs.cmp(&a.unused_secs));
+
+    let mut evicted = Vec::new();
+    for entry in entries {
+        if total_bytes <= max_bytes {
+            break;
+        }
+        total_bytes = total_bytes.saturating_sub(entry.estimated_bytes);
+        evicted.push(entry.id);
+    }
+    evicted
+}
+
+/// Joins a page's resolved scriptlet injections, each as `(script, filter)`,
+/// into the JavaScript string returned by `get_scriptlet_injections`. When
+/// `debug` is true, every scriptlet is preceded by a console log naming the
+/// filter that produced it; the set of injected scripts themselves is
+/// identical either way, since both modes resolve from the same input.
+fn format_scriptlet_injections(injections: &[(String, Option<String>)], url: &str, debug: bool) -> String {
+    injections
+        .iter()
+        .map(|(script, filter)| {
+            if debug {
+                let filter = filter.as_deref().unwrap_or("<unknown filter>");
+                format!("console.log({url:?}, {filter:?}, '[brave] scriptlet injected');\n{script}")
+            } else {
+                script.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Returns the other uBO-style naming convention for a scriptlet resource
+/// name: stripping `.js` if present, or appending it otherwise.
+fn js_suffix_variant(name: &str) -> String {
+    match name.strip_suffix(".js") {
+        Some(stripped) => stripped.to_string(),
+        None => format!("{name}.js"),
+    }
+}
+
+/// Expands a resource's aliases so a rule token resolves the resource
+/// regardless of whether it (or its aliases) are referenced with or without
+/// the `.js` suffix, matching the naming convention used by scriptlet
+/// resources. A mismatch here otherwise causes `+js(...)` injections to
+/// silently fail.
+fn with_js_suffix_aliases(name: &str, mut aliases: Vec<String>) -> Vec<String> {
+    aliases.push(js_suffix_variant(name));
+    let extra_variants: Vec<String> = aliases.iter().map(|alias| js_suffix_variant(alias)).collect();
+    aliases.extend(extra_variants);
+    aliases.retain(|alias| alias != name);
+    aliases.sort_unstable();
+    aliases.dedup();
+    aliases
+}
+
 impl Engine {
     /// Enables a given tag for the engine.
     ///
@@ -235,19 +515,48 @@ impl Engine {
     ) -> BlockerResult {
         // The following strings are guaranteed to be
         // UTF-8, so unwrapping directly should be okay.
-        self.engine
-            .check_network_request_subset(
-                &adblock::request::Request::preparsed(
-                    url.to_str().unwrap(),
-                    hostname.to_str().unwrap(),
-                    source_hostname.to_str().unwrap(),
-                    request_type.to_str().unwrap(),
-                    third_party_request,
-                ),
-                previously_matched_rule,
-                force_check_exceptions,
-            )
-            .into()
+        let req = Request(adblock::request::Request::preparsed(
+            url.to_str().unwrap(),
+            hostname.to_str().unwrap(),
+            source_hostname.to_str().unwrap(),
+            request_type.to_str().unwrap(),
+            third_party_request,
+        ));
+        self.matches_request(&req, previously_matched_rule, force_check_exceptions)
+    }
+
+    /// Checks if an already-parsed request should be blocked and returns an
+    /// evaluation result. Equivalent to `matches`, but reuses a `Request`
+    /// parsed once via `new_request` instead of re-deriving it.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - The previously parsed request.
+    /// * `previously_matched_rule` - Whether a rule matched previously.
+    /// * `force_check_exceptions` - Whether to force checking for exceptions.
+    ///
+    /// # Returns
+    ///
+    /// A `BlockerResult` struct with information on a matching rule and actions.
+    pub fn matches_request(
+        &self,
+        req: &Request,
+        previously_matched_rule: bool,
+        force_check_exceptions: bool,
+    ) -> BlockerResult {
+        self.stats.total_checks.fetch_add(1, Ordering::Relaxed);
+        let raw =
+            self.engine.check_network_request_subset(&req.0, previously_matched_rule, force_check_exceptions);
+        if raw.matched {
+            self.stats.total_matches.fetch_add(1, Ordering::Relaxed);
+        }
+        if raw.has_exception {
+            self.stats.exception_hits.fetch_add(1, Ordering::Relaxed);
+        }
+        let filter = raw.filter.clone();
+        let mut result: BlockerResult = raw.into();
+        result.filter = filter.into();
+        result
     }
 
     /// Returns additional CSP directives to be added to a web response, if applicable.
@@ -272,15 +581,136 @@ impl Engine {
         third_party_request: bool,
     ) -> String {
         // The following strings are also UTF-8.
-        self.engine
-            .get_csp_directives(&adblock::request::Request::preparsed(
-                url.to_str().unwrap(),
-                hostname.to_str().unwrap(),
-                source_hostname.to_str().unwrap(),
-                request_type.to_str().unwrap(),
-                third_party_request,
-            ))
-            .unwrap_or_default()
+        let req = Request(adblock::request::Request::preparsed(
+            url.to_str().unwrap(),
+            hostname.to_str().unwrap(),
+            source_hostname.to_str().unwrap(),
+            request_type.to_str().unwrap(),
+            third_party_request,
+        ));
+        self.get_csp_directives_for_request(&req)
+    }
+
+    /// Returns additional CSP directives for an already-parsed request.
+    /// Equivalent to `get_csp_directives`, but reuses a `Request` parsed once
+    /// via `new_request` instead of re-deriving it.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - The previously parsed request.
+    ///
+    /// # Returns
+    ///
+    /// A `String` containing the CSP directives.
+    pub fn get_csp_directives_for_request(&self, req: &Request) -> String {
+        self.engine.get_csp_directives(&req.0).unwrap_or_default()
+    }
+
+    /// Returns the individual CSP directive fragments that apply to an
+    /// already-parsed request, each paired with the filter that produced it,
+    /// instead of a single semicolon-joined string with no rule attribution.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - The previously parsed request.
+    ///
+    /// # Returns
+    ///
+    /// A `CspResult` with one directive fragment per entry, and the filter
+    /// that produced it.
+    pub fn get_csp_directives_detailed(&self, req: &Request) -> CspResult {
+        // Unlike `get_csp_directives`, which pre-merges every matching
+        // `$csp` rule's directive into one semicolon-joined string, this
+        // walks the individual rule matches so each directive can be paired
+        // with the filter that produced it.
+        let (directives, filters) = self
+            .engine
+            .matching_csp_rules(&req.0)
+            .into_iter()
+            .map(|rule_match| (rule_match.directive, rule_match.filter.into()))
+            .unzip();
+        CspResult { directives, filters }
+    }
+
+    /// Checks a batch of already-parsed requests in a single bridge crossing,
+    /// returning one `BlockerResult` per input in order. Useful when a
+    /// navigation queues up many near-simultaneous subresource checks, since
+    /// it amortizes the per-call FFI overhead of `matches`.
+    ///
+    /// # Arguments
+    ///
+    /// * `requests` - The previously parsed requests, in order.
+    /// * `force_check_exceptions` - Whether to force checking for exceptions.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<BlockerResult>` with one entry per input request.
+    pub fn matches_batch(
+        &self,
+        requests: Vec<Box<Request>>,
+        force_check_exceptions: bool,
+    ) -> Vec<BlockerResult> {
+        // Each entry in a batch is an independent request, so there is no
+        // prior match within the batch to carry forward.
+        requests
+            .iter()
+            .map(|req| self.matches_request(req, false, force_check_exceptions))
+            .collect()
+    }
+
+    /// String-based counterpart to `matches_batch`, for callers that haven't
+    /// pre-parsed their requests via `new_request`. Parallel vectors of
+    /// request fields are walked in a single contiguous pass, building one
+    /// `adblock::request::Request` per entry, so the regex cache stays warm
+    /// across the whole batch.
+    ///
+    /// # Arguments
+    ///
+    /// * `urls` - The URLs of the requests, in order.
+    /// * `hostnames` - The hostnames of the requests, in order.
+    /// * `source_hostnames` - The hostnames of the initiating pages, in order.
+    /// * `request_types` - The types of the requests, in order.
+    /// * `third_party_flags` - One packed byte per request: non-zero if third-party.
+    /// * `force_check_exceptions` - Whether to force checking for exceptions.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<BlockerResult>` with one entry per input request, in order.
+    pub fn matches_batch_raw(
+        &self,
+        urls: &CxxVector<CxxString>,
+        hostnames: &CxxVector<CxxString>,
+        source_hostnames: &CxxVector<CxxString>,
+        request_types: &CxxVector<CxxString>,
+        third_party_flags: &CxxVector<u8>,
+        force_check_exceptions: bool,
+    ) -> Vec<BlockerResult> {
+        let len = urls.len();
+        // The five vectors are meant to be parallel arrays describing the
+        // same batch of requests; if a caller built them with mismatched
+        // lengths, indexing past the end of the shortest one would panic
+        // and abort the process. Fail soft with an empty result instead.
+        if !parallel_batch_lengths_match(
+            len,
+            &[hostnames.len(), source_hostnames.len(), request_types.len(), third_party_flags.len()],
+        ) {
+            return Vec::new();
+        }
+
+        let mut results = Vec::with_capacity(len);
+        for i in 0..len {
+            // The following strings are guaranteed to be UTF-8, so unwrapping
+            // directly should be okay.
+            let req = Request(adblock::request::Request::preparsed(
+                urls.get(i).unwrap().to_str().unwrap(),
+                hostnames.get(i).unwrap().to_str().unwrap(),
+                source_hostnames.get(i).unwrap().to_str().unwrap(),
+                request_types.get(i).unwrap().to_str().unwrap(),
+                *third_party_flags.get(i).unwrap() != 0,
+            ));
+            results.push(self.matches_request(&req, false, force_check_exceptions));
+        }
+        results
     }
 
     /// Serializes the engine state to a byte vector.
@@ -334,6 +764,138 @@ impl Engine {
             .is_some()
     }
 
+    /// Inserts or replaces a single resource in the engine's resource set,
+    /// without rebuilding the whole storage. A resource referenced by a
+    /// `+js(name)` rule resolves whether `name` is registered with or without
+    /// the uBO-style `.js` suffix, and likewise for its aliases.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The resource name.
+    /// * `content_type` - The MIME type of the resource content.
+    /// * `content_base64` - The base64-encoded resource content.
+    /// * `aliases` - Additional names this resource should resolve under.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the resource was added successfully, `false` otherwise.
+    pub fn add_resource(
+        &mut self,
+        name: &CxxString,
+        content_type: &CxxString,
+        content_base64: &CxxString,
+        aliases: &CxxVector<CxxString>,
+    ) -> bool {
+        || -> Result<Resource, Utf8Error> {
+            let name = name.to_str()?.to_string();
+            let aliases: Vec<String> = convert_cxx_string_vector_to_string_collection(aliases)?;
+            Ok(Resource {
+                aliases: with_js_suffix_aliases(&name, aliases),
+                name,
+                kind: ResourceType::Mime(MimeType::from(content_type.to_str()?)),
+                content: content_base64.to_str()?.to_string(),
+            })
+        }()
+        .ok()
+        .map(|resource| self.engine.add_resource(resource).is_ok())
+        .unwrap_or(false)
+    }
+
+    /// Removes a resource from the engine's resource set by name, trying
+    /// both the bare and `.js`-suffixed forms of the name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The resource name.
+    ///
+    /// # Returns
+    ///
+    /// `true` if a matching resource was found and removed.
+    pub fn remove_resource(&mut self, name: &CxxString) -> bool {
+        let Ok(name) = name.to_str() else {
+            return false;
+        };
+        self.engine.remove_resource(name).is_ok()
+            || self.engine.remove_resource(&js_suffix_variant(name)).is_ok()
+    }
+
+    /// Looks up a single resource in the engine's resource set by name,
+    /// trying both the bare and `.js`-suffixed forms of the name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The resource name.
+    ///
+    /// # Returns
+    ///
+    /// An `OptionalResource` containing the resource if one was found.
+    pub fn get_resource(&self, name: &CxxString) -> OptionalResource {
+        let Ok(name) = name.to_str() else {
+            return OptionalResource::default();
+        };
+        let resource =
+            self.engine.get_resource(name).or_else(|| self.engine.get_resource(&js_suffix_variant(name)));
+        match resource {
+            Some(resource) => OptionalResource {
+                has_value: true,
+                value: ResourceInfo {
+                    name: resource.name,
+                    content_type: match resource.kind {
+                        ResourceType::Mime(mime) => mime.to_string(),
+                        _ => String::new(),
+                    },
+                    content_base64: resource.content,
+                    aliases: resource.aliases,
+                },
+            },
+            None => OptionalResource::default(),
+        }
+    }
+
+    /// Resolves a `redirect=`/`redirect-rule=` network rule's named resource
+    /// through the resource storage and returns a ready-to-serve `data:` URL
+    /// (mime + base64 body), so the caller can satisfy a blocked-and-
+    /// redirected request by injecting a stub script, 1x1 image, or empty
+    /// response.
+    ///
+    /// # Arguments
+    ///
+    /// * `request_type` - The type of the redirected request (e.g., "script", "image").
+    /// * `redirect_name` - The name of the redirect resource, as given by the matching rule.
+    ///
+    /// # Returns
+    ///
+    /// An `OptionalString` containing the `data:` URL, if the resource was found.
+    pub fn get_redirect_resource(
+        &self,
+        request_type: &CxxString,
+        redirect_name: &CxxString,
+    ) -> OptionalString {
+        let Ok(redirect_name) = redirect_name.to_str() else {
+            return OptionalString::default();
+        };
+        let resource = self
+            .engine
+            .get_resource(redirect_name)
+            .or_else(|| self.engine.get_resource(&js_suffix_variant(redirect_name)));
+        match resource {
+            Some(resource) => {
+                let mime = match resource.kind {
+                    ResourceType::Mime(mime) => mime.to_string(),
+                    // Some redirect resources (e.g. uBO's `Template` resources)
+                    // carry no explicit mime; fall back to a sensible default
+                    // for the type of request being redirected.
+                    _ => default_mime_for_request_type(request_type.to_str().unwrap_or("")).to_string(),
+                };
+                OptionalString {
+                    has_value: true,
+                    value: format!("data:{mime};base64,{}", resource.content),
+                }
+            }
+            None => OptionalString::default(),
+        }
+    }
+
     /// Returns JSON-serialized cosmetic filter resources for a given url.
     ///
     /// # Arguments
@@ -348,6 +910,48 @@ impl Engine {
         serde_json::to_string(&resources).unwrap()
     }
 
+    /// Returns the concatenated, argument-substituted JavaScript for all
+    /// `##+js(...)` rules matching a given url, resolved via the resource
+    /// storage and its aliases, ready to inject into the page. When
+    /// scriptlet debug logging is enabled (see `set_scriptlet_debug`), each
+    /// scriptlet is additionally preceded by a console log identifying the
+    /// filter that injected it; the set of scripts that actually get
+    /// injected is unaffected by this toggle.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL to check for scriptlet injections.
+    ///
+    /// # Returns
+    ///
+    /// A `String` containing the JavaScript to inject.
+    pub fn get_scriptlet_injections(&self, url: &CxxString) -> String {
+        let url = url.to_str().unwrap();
+        // Both modes resolve scriptlets through the same
+        // `matching_scriptlet_resources` call, so enabling debug logging
+        // can only add console logs — it can never change which scriptlets
+        // actually get injected.
+        let injections: Vec<(String, Option<String>)> = self
+            .engine
+            .matching_scriptlet_resources(url)
+            .into_iter()
+            .map(|injection| (injection.script, injection.filter))
+            .collect();
+        format_scriptlet_injections(&injections, url, self.scriptlet_debug.load(Ordering::Relaxed))
+    }
+
+    /// Enables or disables scriptlet debug logging. When enabled,
+    /// `get_scriptlet_injections` precedes each injected scriptlet with a
+    /// console log identifying the filter that produced it, so filter-list
+    /// authors can see which scriptlets fired for a page.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether scriptlet debug logging should be enabled.
+    pub fn set_scriptlet_debug(&mut self, enabled: bool) {
+        self.scriptlet_debug.store(enabled, Ordering::Relaxed);
+    }
+
     /// Returns list of CSS selectors that require a generic CSS hide rule.
     ///
     /// # Arguments
@@ -381,7 +985,54 @@ impl Engine {
     ///
     /// A `DebugInfo` struct containing regex data.
     pub fn get_debug_info(&self) -> DebugInfo {
-        self.engine.get_debug_info().into()
+        let mut debug_info: DebugInfo = self.engine.get_debug_info().into();
+        for entry in debug_info.regex_data.iter_mut() {
+            entry.estimated_bytes = estimate_regex_bytes(&entry.regex);
+        }
+        debug_info.total_estimated_regex_bytes = debug_info
+            .regex_data
+            .iter()
+            .map(|entry| entry.estimated_bytes)
+            .sum();
+        debug_info
+    }
+
+    /// Evicts the least-recently-used compiled regexes, discarding them until
+    /// the estimated compiled-regex memory footprint fits within `max_bytes`.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_bytes` - The estimated compiled-regex memory budget, in bytes.
+    ///
+    /// # Returns
+    ///
+    /// The number of regexes that were evicted.
+    pub fn enforce_regex_budget(&mut self, max_bytes: usize) -> u32 {
+        let debug_info = self.get_debug_info();
+        let ids_to_evict = select_regexes_to_evict(
+            debug_info.regex_data,
+            debug_info.total_estimated_regex_bytes,
+            max_bytes,
+        );
+        for id in &ids_to_evict {
+            self.engine.discard_regex(*id);
+        }
+        ids_to_evict.len() as u32
+    }
+
+    /// Returns cumulative, engine-wide match statistics collected over the
+    /// lifetime of the engine, for `brave://adblock`-style debugging. The
+    /// counters are totals; they are not broken down per filter or per list.
+    ///
+    /// # Returns
+    ///
+    /// A `MatchStats` struct containing the cumulative counters.
+    pub fn get_blocker_debug_info(&self) -> MatchStats {
+        MatchStats {
+            total_checks: self.stats.total_checks.load(Ordering::Relaxed),
+            total_matches: self.stats.total_matches.load(Ordering::Relaxed),
+            exception_hits: self.stats.exception_hits.load(Ordering::Relaxed),
+        }
     }
 
     /// Removes a regex entry by the id.
@@ -402,3 +1053,122 @@ impl Engine {
         self.engine.set_regex_discard_policy(new_discard_policy.into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn js_suffix_aliases_adds_missing_suffix_variant() {
+        let aliases = with_js_suffix_aliases("foo.js", vec!["bar".to_string()]);
+        assert!(aliases.contains(&"foo".to_string()));
+        assert!(aliases.contains(&"bar.js".to_string()));
+    }
+
+    #[test]
+    fn js_suffix_aliases_strips_present_suffix_variant() {
+        let aliases = with_js_suffix_aliases("foo", vec!["bar.js".to_string()]);
+        assert!(aliases.contains(&"foo.js".to_string()));
+        assert!(aliases.contains(&"bar".to_string()));
+    }
+
+    #[test]
+    fn js_suffix_aliases_excludes_the_name_itself_and_dedups() {
+        let aliases = with_js_suffix_aliases("foo.js", vec!["foo".to_string(), "foo".to_string()]);
+        assert!(!aliases.contains(&"foo.js".to_string()));
+        assert_eq!(aliases.iter().filter(|alias| *alias == "foo").count(), 1);
+    }
+
+    #[cfg(feature = "ios")]
+    #[test]
+    fn clamp_max_rules_per_chunk_enforces_minimum_of_two() {
+        assert_eq!(clamp_max_rules_per_chunk(0), 2);
+        assert_eq!(clamp_max_rules_per_chunk(1), 2);
+        assert_eq!(clamp_max_rules_per_chunk(2), 2);
+        assert_eq!(clamp_max_rules_per_chunk(10), 10);
+    }
+
+    #[cfg(feature = "ios")]
+    #[test]
+    fn chunk_content_blocking_rules_splits_and_appends_exception_rule() {
+        let cb_rules = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let chunks = chunk_content_blocking_rules(cb_rules, "exception".to_string(), 2).unwrap();
+        // max_rules_per_chunk of 2 leaves room for 1 rule plus the
+        // exception, so 3 rules split into 3 single-rule chunks.
+        assert_eq!(chunks.len(), 3);
+        for chunk in &chunks {
+            let parsed: Vec<String> = serde_json::from_str(chunk).unwrap();
+            assert_eq!(parsed.len(), 2);
+            assert_eq!(parsed.last().unwrap(), "exception");
+        }
+    }
+
+    #[cfg(feature = "ios")]
+    #[test]
+    fn chunk_content_blocking_rules_emits_single_chunk_when_only_exception_rule_compiled() {
+        let chunks = chunk_content_blocking_rules(Vec::<String>::new(), "exception".to_string(), 2).unwrap();
+        assert_eq!(chunks.len(), 1);
+        let parsed: Vec<String> = serde_json::from_str(&chunks[0]).unwrap();
+        assert_eq!(parsed, vec!["exception".to_string()]);
+    }
+
+    #[test]
+    fn format_scriptlet_injections_debug_mode_preserves_non_debug_scripts() {
+        let injections = vec![
+            ("console.log(1);".to_string(), Some("filter-a.txt".to_string())),
+            ("console.log(2);".to_string(), None),
+        ];
+        let url = "https://example.com/";
+
+        let non_debug = format_scriptlet_injections(&injections, url, false);
+        let debug = format_scriptlet_injections(&injections, url, true);
+
+        // Debug mode must inject the exact same scripts as non-debug mode;
+        // it may only add console logs around them.
+        for (script, _) in &injections {
+            assert!(non_debug.contains(script));
+            assert!(debug.contains(script));
+        }
+        assert!(debug.contains("filter-a.txt"));
+        assert!(debug.contains("<unknown filter>"));
+        assert!(!non_debug.contains("console.log(\"https"));
+    }
+
+    #[test]
+    fn parallel_batch_lengths_match_accepts_equal_lengths() {
+        assert!(parallel_batch_lengths_match(3, &[3, 3, 3, 3]));
+    }
+
+    #[test]
+    fn parallel_batch_lengths_match_rejects_any_mismatch() {
+        assert!(!parallel_batch_lengths_match(3, &[3, 2, 3, 3]));
+    }
+
+    fn regex_entry(id: u64, unused_secs: u64, estimated_bytes: usize) -> RegexDebugEntry {
+        RegexDebugEntry { id, regex: OptionalString::default(), unused_secs, usage_count: 0, estimated_bytes }
+    }
+
+    #[test]
+    fn select_regexes_to_evict_does_nothing_under_budget() {
+        let entries = vec![regex_entry(1, 100, 50)];
+        assert!(select_regexes_to_evict(entries, 50, 100).is_empty());
+    }
+
+    #[test]
+    fn select_regexes_to_evict_prefers_least_recently_used_first() {
+        let entries =
+            vec![regex_entry(1, 10, 40), regex_entry(2, 100, 40), regex_entry(3, 50, 40)];
+        // Total is 120 bytes over a 50-byte budget: the two least-recently-used
+        // entries (by descending unused_secs: id 2, then id 3) must be evicted
+        // before the budget is met, while the most recently used (id 1) stays.
+        let evicted = select_regexes_to_evict(entries, 120, 50);
+        assert_eq!(evicted, vec![2, 3]);
+    }
+
+    #[test]
+    fn select_regexes_to_evict_stops_as_soon_as_budget_is_met() {
+        let entries = vec![regex_entry(1, 10, 100), regex_entry(2, 5, 100)];
+        let evicted = select_regexes_to_evict(entries, 200, 150);
+        assert_eq!(evicted, vec![1]);
+    }
+}
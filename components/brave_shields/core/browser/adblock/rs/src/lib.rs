@@ -9,11 +9,13 @@
 mod convert;
 mod engine;
 mod filter_set;
+mod request;
 mod resource_storage;
 mod result;
 
 use engine::*;
 use filter_set::*;
+use request::*;
 
 #[allow(unsafe_op_in_unsafe_fn)]
 #[cxx::bridge(namespace = adblock)]
@@ -56,6 +58,29 @@ mod ffi {
             permission_mask: u8,
         ) -> FilterListMetadataResult;
     }
+    extern "Rust" {
+        /// A request parsed once and reusable across multiple engine queries.
+        type Request;
+
+        /// Parses a URL into a reusable `Request`.
+        ///
+        /// # Arguments
+        ///
+        /// * `url` - The URL of the request.
+        /// * `source_url` - The URL of the page initiating the request.
+        /// * `request_type` - The type of request (e.g., "script", "image").
+        /// * `third_party` - Whether the request is third-party.
+        ///
+        /// # Returns
+        ///
+        /// A `BoxRequestResult` containing the parsed request or an error.
+        fn new_request(
+            url: &CxxString,
+            source_url: &CxxString,
+            request_type: &CxxString,
+            third_party: bool,
+        ) -> BoxRequestResult;
+    }
     extern "Rust" {
         /// The ad blocking engine.
         type Engine;
@@ -180,6 +205,106 @@ mod ffi {
             third_party_request: bool,
         ) -> String;
 
+        /// Checks if an already-parsed request should be blocked and returns an
+        /// evaluation result. Equivalent to `matches`, but reuses a `Request`
+        /// parsed once via `new_request` instead of re-deriving it.
+        ///
+        /// # Arguments
+        ///
+        /// * `req` - The previously parsed request.
+        /// * `previously_matched_rule` - Whether a rule matched previously.
+        /// * `force_check_exceptions` - Whether to force checking for exceptions.
+        ///
+        /// # Returns
+        ///
+        /// A `BlockerResult` struct with information on a matching rule and actions.
+        fn matches_request(
+            &self,
+            req: &Request,
+            previously_matched_rule: bool,
+            force_check_exceptions: bool,
+        ) -> BlockerResult;
+
+        /// Returns additional CSP directives for an already-parsed request.
+        /// Equivalent to `get_csp_directives`, but reuses a `Request` parsed
+        /// once via `new_request` instead of re-deriving it.
+        ///
+        /// # Arguments
+        ///
+        /// * `req` - The previously parsed request.
+        ///
+        /// # Returns
+        ///
+        /// A `String` containing the CSP directives.
+        fn get_csp_directives_for_request(&self, req: &Request) -> String;
+
+        /// Returns the individual CSP directive fragments that apply to an
+        /// already-parsed request, each paired with the filter that produced
+        /// it, instead of a single semicolon-joined string with no rule
+        /// attribution. This lets a caller log enforcement, deduplicate
+        /// directives, or merge them with a page's own policy without
+        /// re-parsing `get_csp_directives`'s output.
+        ///
+        /// # Arguments
+        ///
+        /// * `req` - The previously parsed request.
+        ///
+        /// # Returns
+        ///
+        /// A `CspResult` with one directive fragment per entry, and the
+        /// filter that produced it.
+        fn get_csp_directives_detailed(&self, req: &Request) -> CspResult;
+
+        /// Checks a batch of already-parsed requests in a single bridge
+        /// crossing, returning one `BlockerResult` per input in order. Useful
+        /// when a navigation queues up many near-simultaneous subresource
+        /// checks, since it amortizes the per-call FFI overhead of `matches`.
+        ///
+        /// # Arguments
+        ///
+        /// * `requests` - The previously parsed requests, in order. Passed as
+        ///   owned boxes rather than a `CxxVector<Request>`, since `Request`
+        ///   is an opaque Rust type with no C++ definition and so can't be
+        ///   stored in a real `std::vector`.
+        /// * `force_check_exceptions` - Whether to force checking for exceptions.
+        ///
+        /// # Returns
+        ///
+        /// A `Vec<BlockerResult>` with one entry per input request.
+        fn matches_batch(
+            &self,
+            requests: Vec<Box<Request>>,
+            force_check_exceptions: bool,
+        ) -> Vec<BlockerResult>;
+
+        /// String-based counterpart to `matches_batch`, for callers that
+        /// haven't pre-parsed their requests via `new_request`. Parallel
+        /// vectors of request fields are walked in a single contiguous pass,
+        /// building one `adblock::request::Request` per entry, so the regex
+        /// cache stays warm across the whole batch.
+        ///
+        /// # Arguments
+        ///
+        /// * `urls` - The URLs of the requests, in order.
+        /// * `hostnames` - The hostnames of the requests, in order.
+        /// * `source_hostnames` - The hostnames of the initiating pages, in order.
+        /// * `request_types` - The types of the requests, in order.
+        /// * `third_party_flags` - One packed byte per request: non-zero if third-party.
+        /// * `force_check_exceptions` - Whether to force checking for exceptions.
+        ///
+        /// # Returns
+        ///
+        /// A `Vec<BlockerResult>` with one entry per input request, in order.
+        fn matches_batch_raw(
+            &self,
+            urls: &CxxVector<CxxString>,
+            hostnames: &CxxVector<CxxString>,
+            source_hostnames: &CxxVector<CxxString>,
+            request_types: &CxxVector<CxxString>,
+            third_party_flags: &CxxVector<u8>,
+            force_check_exceptions: bool,
+        ) -> Vec<BlockerResult>;
+
         /// Serializes the engine state to a byte vector.
         ///
         /// # Returns
@@ -209,6 +334,73 @@ mod ffi {
         /// `true` if resources were loaded successfully, `false` otherwise.
         fn use_resources(&mut self, resources_json: &CxxString) -> bool;
 
+        /// Inserts or replaces a single resource in the engine's resource set,
+        /// without rebuilding the whole storage. A resource referenced by a
+        /// `+js(name)` rule resolves whether `name` is registered with or
+        /// without the uBO-style `.js` suffix, and likewise for its aliases.
+        ///
+        /// # Arguments
+        ///
+        /// * `name` - The resource name.
+        /// * `content_type` - The MIME type of the resource content.
+        /// * `content_base64` - The base64-encoded resource content.
+        /// * `aliases` - Additional names this resource should resolve under.
+        ///
+        /// # Returns
+        ///
+        /// `true` if the resource was added successfully, `false` otherwise.
+        fn add_resource(
+            &mut self,
+            name: &CxxString,
+            content_type: &CxxString,
+            content_base64: &CxxString,
+            aliases: &CxxVector<CxxString>,
+        ) -> bool;
+
+        /// Removes a resource from the engine's resource set by name, trying
+        /// both the bare and `.js`-suffixed forms of the name.
+        ///
+        /// # Arguments
+        ///
+        /// * `name` - The resource name.
+        ///
+        /// # Returns
+        ///
+        /// `true` if a matching resource was found and removed.
+        fn remove_resource(&mut self, name: &CxxString) -> bool;
+
+        /// Looks up a single resource in the engine's resource set by name,
+        /// trying both the bare and `.js`-suffixed forms of the name.
+        ///
+        /// # Arguments
+        ///
+        /// * `name` - The resource name.
+        ///
+        /// # Returns
+        ///
+        /// An `OptionalResource` containing the resource if one was found.
+        fn get_resource(&self, name: &CxxString) -> OptionalResource;
+
+        /// Resolves a `redirect=`/`redirect-rule=` network rule's named
+        /// resource through the resource storage and returns a ready-to-serve
+        /// `data:` URL (mime + base64 body), so the caller can satisfy a
+        /// blocked-and-redirected request by injecting a stub script, 1x1
+        /// image, or empty response.
+        ///
+        /// # Arguments
+        ///
+        /// * `request_type` - The type of the redirected request (e.g., "script", "image").
+        /// * `redirect_name` - The name of the redirect resource, as given by the matching rule.
+        ///
+        /// # Returns
+        ///
+        /// An `OptionalString` containing the `data:` URL, if the resource was found.
+        fn get_redirect_resource(
+            &self,
+            request_type: &CxxString,
+            redirect_name: &CxxString,
+        ) -> OptionalString;
+
         /// Returns JSON-serialized cosmetic filter resources for a given url.
         ///
         /// # Arguments
@@ -220,6 +412,34 @@ mod ffi {
         /// A `String` containing the JSON-serialized cosmetic resources.
         fn url_cosmetic_resources(&self, url: &CxxString) -> String;
 
+        /// Returns the concatenated, argument-substituted JavaScript for all
+        /// `##+js(...)` rules matching a given url, resolved via the
+        /// resource storage and its aliases, ready to inject into the page.
+        /// When scriptlet debug logging is enabled (see
+        /// `set_scriptlet_debug`), each scriptlet is additionally preceded
+        /// by a console log identifying the filter that injected it; the
+        /// set of scripts that actually get injected is unaffected by this
+        /// toggle.
+        ///
+        /// # Arguments
+        ///
+        /// * `url` - The URL to check for scriptlet injections.
+        ///
+        /// # Returns
+        ///
+        /// A `String` containing the JavaScript to inject.
+        fn get_scriptlet_injections(&self, url: &CxxString) -> String;
+
+        /// Enables or disables scriptlet debug logging. When enabled,
+        /// `get_scriptlet_injections` precedes each injected scriptlet with a
+        /// console log identifying the filter that produced it, so
+        /// filter-list authors can see which scriptlets fired for a page.
+        ///
+        /// # Arguments
+        ///
+        /// * `enabled` - Whether scriptlet debug logging should be enabled.
+        fn set_scriptlet_debug(&mut self, enabled: bool);
+
         /// Returns list of CSS selectors that require a generic CSS hide rule.
         ///
         /// # Arguments
@@ -245,6 +465,15 @@ mod ffi {
         /// A `DebugInfo` struct containing regex data.
         fn get_debug_info(&self) -> DebugInfo;
 
+        /// Returns cumulative, engine-wide match statistics collected over the
+        /// lifetime of the engine, for `brave://adblock`-style debugging. The
+        /// counters are totals; they are not broken down per filter or per list.
+        ///
+        /// # Returns
+        ///
+        /// A `MatchStats` struct containing the cumulative counters.
+        fn get_blocker_debug_info(&self) -> MatchStats;
+
         /// Removes a regex entry by the id.
         ///
         /// # Arguments
@@ -259,6 +488,21 @@ mod ffi {
         /// * `new_discard_policy` - The new policy to apply.
         fn set_regex_discard_policy(&mut self, new_discard_policy: &RegexManagerDiscardPolicy);
 
+        /// Evicts the least-recently-used compiled regexes, discarding them
+        /// (to be lazily recompiled on their next match) until the estimated
+        /// compiled-regex memory footprint fits within `max_bytes`. Lets
+        /// low-memory configurations cap compiled-regex RAM without losing
+        /// correctness.
+        ///
+        /// # Arguments
+        ///
+        /// * `max_bytes` - The estimated compiled-regex memory budget, in bytes.
+        ///
+        /// # Returns
+        ///
+        /// The number of regexes that were evicted.
+        fn enforce_regex_budget(&mut self, max_bytes: usize) -> u32;
+
         /// Converts a list in adblock syntax to its corresponding iOS content-blocking syntax.
         ///
         /// `truncated` will be set to indicate whether or not some rules had to be removed
@@ -272,6 +516,47 @@ mod ffi {
         ///
         /// A `ContentBlockingRulesResult` containing the converted rules.
         fn convert_rules_to_content_blocking(rules: &CxxString) -> ContentBlockingRulesResult;
+
+        /// Converts a list in adblock syntax to its corresponding iOS
+        /// content-blocking syntax, splitting the output into as many chunks
+        /// as needed to keep each one at or below `max_rules_per_chunk` rules,
+        /// instead of dropping rules once the cap is hit. Every chunk also
+        /// carries the first-party document exception rule, which consumes
+        /// one of that budget's slots, so `max_rules_per_chunk` is clamped
+        /// to a minimum of 2.
+        ///
+        /// # Arguments
+        ///
+        /// * `rules` - The adblock rules to convert.
+        /// * `max_rules_per_chunk` - The maximum number of rules allowed per
+        ///   chunk, clamped to a minimum of 2 to leave room for the
+        ///   exception rule.
+        ///
+        /// # Returns
+        ///
+        /// A `ContentBlockingRulesChunkedResult` containing one JSON rule list per chunk.
+        fn convert_rules_to_content_blocking_chunked(
+            rules: &CxxString,
+            max_rules_per_chunk: u32,
+        ) -> ContentBlockingRulesChunkedResult;
+
+        /// Converts a list in adblock syntax into as many
+        /// WebKit-maximum-sized content-blocking rule lists as needed so that
+        /// registering several content-blocker extensions loses no rules,
+        /// instead of truncating at `MAX_CB_LIST_SIZE`. Equivalent to calling
+        /// `convert_rules_to_content_blocking_chunked` with that constant and
+        /// wrapping each chunk as a `ContentBlockingRules`.
+        ///
+        /// # Arguments
+        ///
+        /// * `rules` - The adblock rules to convert.
+        ///
+        /// # Returns
+        ///
+        /// A `ContentBlockingRulesSplitResult` containing one rule list per chunk.
+        fn convert_rules_to_content_blocking_split(
+            rules: &CxxString,
+        ) -> ContentBlockingRulesSplitResult;
     }
 
     unsafe extern "C++" {
@@ -301,6 +586,7 @@ mod ffi {
         matched: bool,
         important: bool,
         has_exception: bool,
+        filter: OptionalString,
         redirect: OptionalString,
         rewritten_url: OptionalString,
     }
@@ -311,6 +597,7 @@ mod ffi {
         regex: OptionalString,
         unused_secs: u64,
         usage_count: usize,
+        estimated_bytes: usize,
     }
 
     /// Debug information for the adblock engine.
@@ -318,6 +605,15 @@ mod ffi {
         regex_data: Vec<RegexDebugEntry>,
         compiled_regex_count: usize,
         flatbuffer_size: usize,
+        total_estimated_regex_bytes: usize,
+    }
+
+    /// Cumulative match counters collected over the lifetime of an engine.
+    #[derive(Default)]
+    struct MatchStats {
+        total_checks: u64,
+        total_matches: u64,
+        exception_hits: u64,
     }
 
     /// Policy for discarding unused regexes.
@@ -341,6 +637,14 @@ mod ffi {
         truncated: bool,
     }
 
+    /// The individual CSP directive fragments that apply to a request, along
+    /// with the filter that produced each one (when known).
+    #[derive(Default)]
+    struct CspResult {
+        directives: Vec<String>,
+        filters: Vec<OptionalString>,
+    }
+
     /// The kind of result returned by FFI functions.
     enum ResultKind {
         Success,
@@ -361,6 +665,20 @@ mod ffi {
         error_message: String,
     }
 
+    /// Result wrapper for a vector of chunked content-blocking rule lists.
+    struct ContentBlockingRulesChunkedResult {
+        value: Vec<String>,
+        result_kind: ResultKind,
+        error_message: String,
+    }
+
+    /// Result wrapper for a vector of `ContentBlockingRules`.
+    struct ContentBlockingRulesSplitResult {
+        value: Vec<ContentBlockingRules>,
+        result_kind: ResultKind,
+        error_message: String,
+    }
+
     /// Result wrapper for a vector of strings.
     struct VecStringResult {
         value: Vec<String>,
@@ -375,6 +693,13 @@ mod ffi {
         error_message: String,
     }
 
+    /// Result wrapper for a `Box<Request>`.
+    struct BoxRequestResult {
+        value: Box<Request>,
+        result_kind: ResultKind,
+        error_message: String,
+    }
+
     /// Result wrapper for `FilterListMetadata`.
     struct FilterListMetadataResult {
         value: FilterListMetadata,
@@ -382,6 +707,16 @@ mod ffi {
         error_message: String,
     }
 
+    /// A single scriptlet/redirect resource looked up from the engine's
+    /// resource set.
+    #[derive(Default)]
+    struct ResourceInfo {
+        name: String,
+        content_type: String,
+        content_base64: String,
+        aliases: Vec<String>,
+    }
+
     // Created custom Option struct because automatic conversion of Option<T>
     // is not yet supported in cxx.
     /// Custom Option struct for String.
@@ -397,4 +732,11 @@ mod ffi {
         has_value: bool,
         value: u16,
     }
+
+    /// Custom Option struct for `ResourceInfo`.
+    #[derive(Default)]
+    struct OptionalResource {
+        has_value: bool,
+        value: ResourceInfo,
+    }
 }
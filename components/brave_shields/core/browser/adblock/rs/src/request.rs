@@ -0,0 +1,48 @@
+/* Copyright (c) 2023 The Brave Authors. All rights reserved.
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this file,
+ * You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use adblock::request::Request as InnerRequest;
+use cxx::CxxString;
+
+use crate::ffi::BoxRequestResult;
+use crate::result::InternalError;
+
+/// A request that has already been parsed into adblock-rust's internal
+/// representation, so that it can be reused across multiple engine queries
+/// (e.g. `matches_request` followed by `get_csp_directives_for_request`)
+/// without re-deriving the hostname, source hostname, and third-party status
+/// each time.
+pub struct Request(pub(crate) InnerRequest);
+
+/// Parses a URL into a reusable `Request`.
+///
+/// # Arguments
+///
+/// * `url` - The URL of the request.
+/// * `source_url` - The URL of the page initiating the request.
+/// * `request_type` - The type of request (e.g., "script", "image").
+/// * `third_party` - Whether the request is third-party.
+///
+/// # Returns
+///
+/// A `BoxRequestResult` containing the parsed request or an error.
+pub fn new_request(
+    url: &CxxString,
+    source_url: &CxxString,
+    request_type: &CxxString,
+    third_party: bool,
+) -> BoxRequestResult {
+    || -> Result<Box<Request>, InternalError> {
+        let mut inner =
+            InnerRequest::new(url.to_str()?, source_url.to_str()?, request_type.to_str()?)?;
+        // The embedder already knows the correct third-party status from
+        // Chromium's own network stack, which is more precise than the
+        // hostname-based heuristic `Request::new` falls back on, so it takes
+        // precedence here.
+        inner.is_third_party = Some(third_party);
+        Ok(Box::new(Request(inner)))
+    }()
+    .into()
+}